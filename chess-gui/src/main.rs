@@ -1,11 +1,146 @@
 use eliasfl_chess::{Color as Colour, Game, GameState, Piece as PieceType, Position};
-use ggez::event::MouseButton;
+use ggez::event::{KeyCode, KeyMods, MouseButton};
 use ggez::graphics::{self, Color, DrawParam};
 use ggez::{event, timer};
 use ggez::{Context, GameResult};
 use std::collections::HashMap;
+use std::fs;
 use std::path;
 
+/// Directory used for saving and loading games.
+const SAVE_DIR: &str = "./resources";
+
+/// Main thinking time given to each player at the start of a game, in seconds.
+const MAIN_TIME: f32 = 10.0 * 60.0;
+/// Seconds added to a player's clock after every move under Fischer increment.
+const FISCHER_INCREMENT: f32 = 5.0;
+/// Number of byo-yomi periods each player starts with.
+const BYOYOMI_PERIODS: u32 = 3;
+/// Length of a single byo-yomi period, in seconds.
+const BYOYOMI_PERIOD: f32 = 30.0;
+/// Number of moves that must be completed within a Canadian overtime block.
+const CANADIAN_MOVES: u32 = 10;
+/// Length of a single Canadian overtime block, in seconds.
+const CANADIAN_BLOCK: f32 = 5.0 * 60.0;
+
+/// The time-control scheme shared by both players.
+#[derive(Clone, Copy, PartialEq)]
+enum TimeControl {
+    /// Sudden death with a fixed increment added to the mover's clock after each move.
+    Fischer { increment: f32 },
+    /// Main time, then a number of fixed-length periods reset by completing a move.
+    ByoYomi { period: f32 },
+    /// Main time, then a block of time in which a fixed number of moves must be made.
+    Canadian { block: f32, moves: u32 },
+}
+
+/// Per-player clock state, interpreted according to the active [`TimeControl`].
+struct PlayerClock {
+    /// Remaining seconds in the current phase (main time, byo-yomi period, or Canadian block).
+    time: f32,
+    /// `true` once the player has used up their main time and entered overtime.
+    in_overtime: bool,
+    /// Byo-yomi periods left (unused by the other modes).
+    periods: u32,
+    /// Moves still to make in the current Canadian block (unused by the other modes).
+    stones: u32,
+    /// `true` once the player has lost on time.
+    flagged: bool,
+}
+
+impl PlayerClock {
+    /// A fresh clock for the given time control.
+    fn new(control: TimeControl) -> PlayerClock {
+        PlayerClock {
+            time: MAIN_TIME,
+            in_overtime: false,
+            periods: match control {
+                TimeControl::ByoYomi { .. } => BYOYOMI_PERIODS,
+                _ => 0,
+            },
+            stones: 0,
+            flagged: false,
+        }
+    }
+
+    /// Advance the clock by `dt` seconds, applying the overtime rules of `control`.
+    fn tick(&mut self, dt: f32, control: TimeControl) {
+        if self.flagged {
+            return;
+        }
+        self.time -= dt;
+        if self.time > 0.0 {
+            return;
+        }
+
+        match control {
+            TimeControl::Fischer { .. } => {
+                self.time = 0.0;
+                self.flagged = true;
+            }
+            TimeControl::ByoYomi { period } => {
+                if !self.in_overtime {
+                    // Main time expired: drop into byo-yomi with a full period.
+                    self.in_overtime = true;
+                    self.time = period;
+                } else if self.periods > 1 {
+                    // A period expired; consume one and start the next.
+                    self.periods -= 1;
+                    self.time = period;
+                } else {
+                    self.time = 0.0;
+                    self.flagged = true;
+                }
+            }
+            TimeControl::Canadian { block, moves } => {
+                if !self.in_overtime {
+                    self.in_overtime = true;
+                    self.time = block;
+                    self.stones = moves;
+                } else {
+                    self.time = 0.0;
+                    self.flagged = true;
+                }
+            }
+        }
+    }
+
+    /// Apply the reward for completing a move (called for the player that just moved).
+    fn on_move(&mut self, control: TimeControl) {
+        match control {
+            TimeControl::Fischer { increment } => self.time += increment,
+            TimeControl::ByoYomi { period } => {
+                if self.in_overtime {
+                    self.time = period;
+                }
+            }
+            TimeControl::Canadian { block, moves } => {
+                if self.in_overtime {
+                    self.stones = self.stones.saturating_sub(1);
+                    if self.stones == 0 {
+                        self.stones = moves;
+                        self.time = block;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Overtime label (periods / stones left) shown beside the digital clock, if any.
+    fn overtime_label(&self, control: TimeControl) -> Option<String> {
+        match control {
+            TimeControl::ByoYomi { .. } if self.in_overtime => {
+                Some(format!("{} periods", self.periods))
+            }
+            TimeControl::Canadian { .. } if self.in_overtime => {
+                Some(format!("{} stones", self.stones))
+            }
+            _ => None,
+        }
+    }
+
+}
+
 /// A chess board is 8x8 tiles.
 const GRID_SIZE: (i16, i16) = (8, 8);
 /// Suitible size of each tile.
@@ -23,14 +158,228 @@ const WHITE: Color = Color::new(188.0 / 255.0, 140.0 / 255.0, 76.0 / 255.0, 1.0)
 const SELECTED_TILE: Color = Color::new(209.0 / 255.0, 161.0 / 255.0, 29.0 / 255.0, 1.0);
 const MOVABLE_TILE: Color = Color::new(209.0 / 255.0, 62.0 / 255.0, 29.0 / 255.0, 1.0);
 
+/// A selectable board colour scheme.
+#[derive(Clone, Copy)]
+struct Palette {
+    light_tile: Color,
+    dark_tile: Color,
+    selected: Color,
+    movable: Color,
+    /// Colour of the lit seven-segment clock digits.
+    clock: Color,
+}
+
+/// Seconds below which the clock display flashes to warn of low time.
+const LOW_TIME_THRESHOLD: f32 = 10.0;
+
+/// Which of the seven segments (a, b, c, d, e, f, g) are lit for each digit 0-9.
+const DIGIT_SEGMENTS: [[bool; 7]; 10] = [
+    [true, true, true, true, true, true, false],    // 0
+    [false, true, true, false, false, false, false], // 1
+    [true, true, false, true, true, false, true],   // 2
+    [true, true, true, true, false, false, true],   // 3
+    [false, true, true, false, false, true, true],  // 4
+    [true, false, true, true, false, true, true],   // 5
+    [true, false, true, true, true, true, true],    // 6
+    [true, true, true, false, false, false, false], // 7
+    [true, true, true, true, true, true, true],     // 8
+    [true, true, true, true, false, true, true],    // 9
+];
+
+/// Named board themes the user can cycle through in the settings overlay.
+const PALETTES: [(&str, Palette); 3] = [
+    (
+        "Wood",
+        Palette {
+            light_tile: WHITE,
+            dark_tile: BLACK,
+            selected: SELECTED_TILE,
+            movable: MOVABLE_TILE,
+            clock: Color::new(0.90, 0.25, 0.20, 1.0),
+        },
+    ),
+    (
+        "Slate",
+        Palette {
+            light_tile: Color::new(0.85, 0.86, 0.82, 1.0),
+            dark_tile: Color::new(0.33, 0.40, 0.35, 1.0),
+            selected: Color::new(0.45, 0.62, 0.40, 1.0),
+            movable: Color::new(0.70, 0.45, 0.35, 1.0),
+            clock: Color::new(0.55, 0.85, 0.55, 1.0),
+        },
+    ),
+    (
+        "Ocean",
+        Palette {
+            light_tile: Color::new(0.79, 0.84, 0.90, 1.0),
+            dark_tile: Color::new(0.28, 0.42, 0.58, 1.0),
+            selected: Color::new(0.36, 0.58, 0.73, 1.0),
+            movable: Color::new(0.80, 0.40, 0.38, 1.0),
+            clock: Color::new(0.55, 0.80, 0.95, 1.0),
+        },
+    ),
+];
+
+/// UI language for the side-panel text.
+#[derive(Clone, Copy, PartialEq)]
+enum Language {
+    English,
+    Japanese,
+}
+
+impl Language {
+    fn reset(&self) -> &'static str {
+        match self {
+            Language::English => "Reset",
+            Language::Japanese => "リセット",
+        }
+    }
+
+    fn time_left(&self) -> &'static str {
+        match self {
+            Language::English => "Time left",
+            Language::Japanese => "残り時間",
+        }
+    }
+
+    fn promote(&self) -> &'static str {
+        match self {
+            Language::English => "Choose piece to promote to:",
+            Language::Japanese => "昇格する駒を選択:",
+        }
+    }
+
+    /// Localised colour name for turn/win/timeout messages.
+    fn colour(&self, colour: Colour) -> &'static str {
+        match (self, colour) {
+            (Language::English, Colour::White) => "White",
+            (Language::English, Colour::Black) => "Black",
+            (Language::Japanese, Colour::White) => "白",
+            (Language::Japanese, Colour::Black) => "黒",
+        }
+    }
+
+    fn turn(&self, colour: Colour) -> String {
+        match self {
+            Language::English => format!("{}'s turn", self.colour(colour)),
+            Language::Japanese => format!("{}の番", self.colour(colour)),
+        }
+    }
+
+    fn won(&self, colour: Colour) -> String {
+        match self {
+            Language::English => format!("{} has won!", self.colour(colour)),
+            Language::Japanese => format!("{}の勝ち!", self.colour(colour)),
+        }
+    }
+
+    fn timeout(&self, colour: Colour) -> String {
+        match self {
+            Language::English => format!("{} has won as the time ran out!", self.colour(colour)),
+            Language::Japanese => format!("時間切れで{}の勝ち!", self.colour(colour)),
+        }
+    }
+}
+
+/// User-configurable presentation and rules, editable through the settings overlay.
+struct Settings {
+    /// Whether the overlay is shown; the clock is paused while it is open.
+    open: bool,
+    /// Index into [`PALETTES`] of the active theme.
+    palette_index: usize,
+    /// Side-panel language.
+    language: Language,
+    /// Active time control; changing it resets both clocks.
+    time_control: TimeControl,
+    /// Automatically flip the board to the side whose turn it is.
+    auto_flip: bool,
+}
+
+impl Settings {
+    fn new() -> Settings {
+        Settings {
+            open: false,
+            palette_index: 0,
+            language: Language::English,
+            time_control: TimeControl::Fischer {
+                increment: FISCHER_INCREMENT,
+            },
+            auto_flip: false,
+        }
+    }
+
+    /// The currently selected palette.
+    fn palette(&self) -> Palette {
+        PALETTES[self.palette_index].1
+    }
+
+    /// Name of the currently selected palette.
+    fn palette_name(&self) -> &'static str {
+        PALETTES[self.palette_index].0
+    }
+
+    fn cycle_palette(&mut self) {
+        self.palette_index = (self.palette_index + 1) % PALETTES.len();
+    }
+
+    fn cycle_language(&mut self) {
+        self.language = match self.language {
+            Language::English => Language::Japanese,
+            Language::Japanese => Language::English,
+        };
+    }
+
+    fn cycle_time_control(&mut self) {
+        self.time_control = match self.time_control {
+            TimeControl::Fischer { .. } => TimeControl::ByoYomi {
+                period: BYOYOMI_PERIOD,
+            },
+            TimeControl::ByoYomi { .. } => TimeControl::Canadian {
+                block: CANADIAN_BLOCK,
+                moves: CANADIAN_MOVES,
+            },
+            TimeControl::Canadian { .. } => TimeControl::Fischer {
+                increment: FISCHER_INCREMENT,
+            },
+        };
+    }
+
+    /// Short label describing the active time control.
+    fn time_control_name(&self) -> &'static str {
+        match self.time_control {
+            TimeControl::Fischer { .. } => "Fischer",
+            TimeControl::ByoYomi { .. } => "Byo-yomi",
+            TimeControl::Canadian { .. } => "Canadian",
+        }
+    }
+}
+
 /// GUI logic and event implementation structure.
 struct AppState {
     sprites: HashMap<PieceType, graphics::Image>,
+    /// Pre-built checkerboard mesh, drawn once per frame instead of 64 fresh rectangles.
+    board_mesh: graphics::Mesh,
+    /// One reusable sprite batch per piece texture, cleared and refilled each frame.
+    sprite_batches: HashMap<PieceType, graphics::spritebatch::SpriteBatch>,
     game: Game,
     selected_tile: Option<Position>,
     highlighted_tiles: Vec<Position>,
-    white_time: f32,
-    black_time: f32,
+    /// User-configurable theme, language and time control.
+    settings: Settings,
+    /// White's clock.
+    white_clock: PlayerClock,
+    /// Black's clock.
+    black_clock: PlayerClock,
+    /// Every move committed so far as `(from, to)` coordinate pairs, used for PGN export.
+    move_log: Vec<(String, String)>,
+    /// Board snapshots (with the colour to move) for every reached position, oldest first.
+    history: Vec<(HashMap<Position, PieceType>, Colour)>,
+    /// Index into `history` of the position currently being viewed.
+    cursor: usize,
+    /// Whether the board is manually drawn from Black's perspective.
+    manual_flip: bool,
+    /// Pieces captured so far, in capture order, used for the side-panel tray.
+    captured: Vec<PieceType>,
 }
 
 impl AppState {
@@ -48,18 +397,227 @@ impl AppState {
             );
         }
 
+        let settings = Settings::new();
+
+        // Build the static checkerboard once and a reusable batch per piece texture.
+        let board_mesh = AppState::build_board_mesh(ctx, settings.palette())?;
+        let mut sprite_batches: HashMap<PieceType, graphics::spritebatch::SpriteBatch> =
+            Default::default();
+        for (piece, image) in loaded_sprites.iter() {
+            sprite_batches.insert(*piece, graphics::spritebatch::SpriteBatch::new(image.clone()));
+        }
+
+        let history = vec![(game.board.clone(), game.active_color)];
+        let time_control = settings.time_control;
         let state = AppState {
             sprites: loaded_sprites,
+            board_mesh,
+            sprite_batches,
             game,
             selected_tile: None,
             highlighted_tiles: Default::default(),
-            white_time: 10.0 * 60.0, // 10 minutes
-            black_time: 10.0 * 60.0,
+            settings,
+            white_clock: PlayerClock::new(time_control),
+            black_clock: PlayerClock::new(time_control),
+            move_log: Default::default(),
+            history,
+            cursor: 0,
+            manual_flip: false,
+            captured: Default::default(),
         };
 
         Ok(state)
     }
 
+    /// Whether the board should currently be drawn from Black's perspective.
+    fn flipped(&self) -> bool {
+        if self.settings.auto_flip {
+            self.game.active_color == Colour::Black
+        } else {
+            self.manual_flip
+        }
+    }
+
+    /// Screen-space top-left corner of the tile at `(file, rank)`, honouring board flipping.
+    fn tile_origin(&self, file: u8, rank: u8) -> (f32, f32) {
+        let (col, row) = if self.flipped() {
+            (8 - file as i32, 8 - rank as i32)
+        } else {
+            (file as i32 - 1, rank as i32 - 1)
+        };
+        (
+            (col * GRID_CELL_SIZE.0 as i32) as f32,
+            (row * GRID_CELL_SIZE.1 as i32) as f32,
+        )
+    }
+
+    /// Build the two-colour checkerboard as a single reusable mesh for the given palette.
+    fn build_board_mesh(ctx: &mut Context, palette: Palette) -> GameResult<graphics::Mesh> {
+        let mut builder = graphics::MeshBuilder::new();
+        for i in 0..64 {
+            builder.rectangle(
+                graphics::DrawMode::fill(),
+                graphics::Rect::new_i32(
+                    i % 8 * GRID_CELL_SIZE.0 as i32,
+                    i / 8 * GRID_CELL_SIZE.1 as i32,
+                    GRID_CELL_SIZE.0 as i32,
+                    GRID_CELL_SIZE.1 as i32,
+                ),
+                checker_colour(i, palette),
+            )?;
+        }
+        builder.build(ctx)
+    }
+
+    /// Reset both clocks to a fresh state for the current time control.
+    fn reset_clocks(&mut self) {
+        self.white_clock = PlayerClock::new(self.settings.time_control);
+        self.black_clock = PlayerClock::new(self.settings.time_control);
+    }
+
+    /// The clock belonging to the given colour.
+    fn clock(&self, colour: Colour) -> &PlayerClock {
+        match colour {
+            Colour::White => &self.white_clock,
+            Colour::Black => &self.black_clock,
+        }
+    }
+
+    /// `true` once either player has lost on time.
+    fn time_over(&self) -> bool {
+        self.white_clock.flagged || self.black_clock.flagged
+    }
+
+    /// Record the live position as a new history entry and move the cursor to it.
+    fn record_snapshot(&mut self) {
+        self.history
+            .push((self.game.board.clone(), self.game.active_color));
+        self.cursor = self.history.len() - 1;
+    }
+
+    /// Reset the history to hold only the current live position.
+    fn reset_history(&mut self) {
+        self.history = vec![(self.game.board.clone(), self.game.active_color)];
+        self.cursor = 0;
+    }
+
+    /// `true` when the playback cursor is on the latest (live) position.
+    fn at_latest(&self) -> bool {
+        self.cursor + 1 == self.history.len()
+    }
+
+    /// Full-move number (increments after each Black move), as used in FEN and PGN.
+    fn fullmove_number(&self) -> usize {
+        self.move_log.len() / 2 + 1
+    }
+
+    /// Serialise the current position as a FEN string (placement, active colour and counters).
+    fn to_fen(&self) -> String {
+        let active = match self.game.active_color {
+            Colour::White => 'w',
+            Colour::Black => 'b',
+        };
+        format!(
+            "{} {} - - 0 {}",
+            placement_from_board(&self.game.board),
+            active,
+            self.fullmove_number()
+        )
+    }
+
+    /// Replace the position with the one described by `fen`.
+    fn load_fen(&mut self, fen: &str) {
+        let mut fields = fen.split_whitespace();
+        let placement = match fields.next() {
+            Some(placement) => placement,
+            None => return,
+        };
+
+        // Start from a fresh game so any state derived by the crate begins valid,
+        // then inject the FEN position rather than mutating a carried-over game.
+        self.game = Game::new();
+        self.game.board = board_from_placement(placement);
+        if let Some(active) = fields.next() {
+            self.game.active_color = if active.starts_with('b') {
+                Colour::Black
+            } else {
+                Colour::White
+            };
+        }
+        self.move_log = Default::default();
+        self.selected_tile = None;
+        self.highlighted_tiles = Default::default();
+        self.captured = Default::default();
+        self.reset_clocks();
+        self.reset_history();
+    }
+
+    /// Serialise the whole game as PGN, with moves written in coordinate notation.
+    fn to_pgn(&self) -> String {
+        let mut pgn = String::from("[Event \"Casual Game\"]\n[Site \"melg-chess-gui\"]\n\n");
+        for (i, (from, to)) in self.move_log.iter().enumerate() {
+            if i % 2 == 0 {
+                pgn.push_str(&format!("{}. ", i / 2 + 1));
+            }
+            pgn.push_str(&format!("{}{} ", from, to));
+        }
+        pgn = pgn.trim_end().to_string();
+        pgn.push('\n');
+        pgn
+    }
+
+    /// Reconstruct a fresh game from `pgn`, replaying every move through `make_move`.
+    fn load_pgn(&mut self, pgn: &str) {
+        self.game = Game::new();
+        self.move_log = Default::default();
+        self.selected_tile = None;
+        self.highlighted_tiles = Default::default();
+        self.captured = Default::default();
+        self.reset_clocks();
+        self.reset_history();
+
+        for line in pgn.lines() {
+            if line.starts_with('[') || line.trim().is_empty() {
+                continue;
+            }
+            for token in line.split_whitespace() {
+                // Skip move numbers such as "1." and stray counters.
+                if token.trim_end_matches('.').chars().all(|c| c.is_ascii_digit()) {
+                    continue;
+                }
+                if token.len() >= 4 {
+                    let from = token[0..2].to_string();
+                    let to = token[2..4].to_string();
+                    if self.game.make_move(from.clone(), to.clone()).is_ok() {
+                        self.move_log.push((from, to));
+                        self.record_snapshot();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Write the current game to `./resources` as both PGN and FEN.
+    fn save_game(&self) {
+        let _ = fs::create_dir_all(SAVE_DIR);
+        let _ = fs::write(format!("{}/save.pgn", SAVE_DIR), self.to_pgn());
+        let _ = fs::write(format!("{}/save.fen", SAVE_DIR), self.to_fen());
+    }
+
+    /// Load a previously saved PGN game from `./resources`, if one exists.
+    fn open_game(&mut self) {
+        if let Ok(pgn) = fs::read_to_string(format!("{}/save.pgn", SAVE_DIR)) {
+            self.load_pgn(&pgn);
+        }
+    }
+
+    /// Set up a position from a FEN file in `./resources`, if one exists.
+    fn open_fen(&mut self) {
+        if let Ok(fen) = fs::read_to_string(format!("{}/save.fen", SAVE_DIR)) {
+            self.load_fen(fen.trim());
+        }
+    }
+
     /// Loads chess piece images into hashmap.
     fn load_sprites() -> HashMap<PieceType, String> {
         let mut sprites = HashMap::new();
@@ -119,16 +677,15 @@ impl AppState {
 impl event::EventHandler<ggez::GameError> for AppState {
     /// For updating game logic, which front-end doesn't handle.
     fn update(&mut self, _ctx: &mut Context) -> GameResult {
-        // Decrease time
-        if self.white_time > 0.0 && self.black_time > 0.0 {
+        // Decrease the active player's clock, applying the current time control's rules.
+        // The clock is paused while the settings overlay is open.
+        if !self.time_over() && !self.settings.open {
+            let dt = timer::delta(_ctx).as_secs_f32();
+            let control = self.settings.time_control;
             match self.game.active_color {
-                Colour::White => self.white_time -= timer::delta(_ctx).as_secs_f32(),
-                Colour::Black => self.black_time -= timer::delta(_ctx).as_secs_f32(),
+                Colour::White => self.white_clock.tick(dt, control),
+                Colour::Black => self.black_clock.tick(dt, control),
             }
-
-            // Prevent negative time
-            self.white_time = self.white_time.max(0.0);
-            self.black_time = self.black_time.max(0.0);
         }
 
         Ok(())
@@ -139,65 +696,60 @@ impl event::EventHandler<ggez::GameError> for AppState {
         // Clear interface with gray background colour
         graphics::clear(ctx, Color::BLUE);
 
-        // Draw tiles
-        for i in 0..64 {
-            let position = &Position {
-                file: (i % 8 + 1) as u8, // Add one as api i 1-8 instead of 0-7
-                rank: (i / 8 + 1) as u8,
-            };
+        // When reviewing history, draw the snapshot at the cursor instead of the live board.
+        let viewed_board = self.history[self.cursor].0.clone();
+
+        // Draw the cached checkerboard in a single call.
+        graphics::draw(
+            ctx,
+            &self.board_mesh,
+            (ggez::mint::Point2 { x: 0.0, y: 0.0 },),
+        )?;
 
-            let colour;
-            if self.selected_tile.is_some() && self.selected_tile.unwrap() == position.to_owned() {
-                colour = SELECTED_TILE;
-            } else if self.highlighted_tiles.contains(position) {
-                colour = MOVABLE_TILE;
+        // Overlay pass: only the selected and movable tiles need a coloured rectangle.
+        for position in self.highlighted_tiles.iter().chain(self.selected_tile.iter()) {
+            let palette = self.settings.palette();
+            let colour = if self.selected_tile.as_ref() == Some(position) {
+                palette.selected
             } else {
-                colour = match i % 2 {
-                    0 => match i / 8 {
-                        _row if _row % 2 == 0 => WHITE,
-                        _ => BLACK,
-                    },
-                    _ => match i / 8 {
-                        _row if _row % 2 == 0 => BLACK,
-                        _ => WHITE,
-                    },
-                };
+                palette.movable
             };
-
+            let (x, y) = self.tile_origin(position.file, position.rank);
             let rectangle = graphics::Mesh::new_rectangle(
                 ctx,
                 graphics::DrawMode::fill(),
-                graphics::Rect::new_i32(
-                    i % 8 * GRID_CELL_SIZE.0 as i32,
-                    i / 8 * GRID_CELL_SIZE.1 as i32,
-                    GRID_CELL_SIZE.0 as i32,
-                    GRID_CELL_SIZE.1 as i32,
-                ),
+                graphics::Rect::new(x, y, GRID_CELL_SIZE.0 as f32, GRID_CELL_SIZE.1 as f32),
                 colour,
             )?;
             graphics::draw(ctx, &rectangle, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+        }
 
-            // Draw piece
-            if self.game.board.contains_key(position) {
-                let sprite = self
-                    .sprites
-                    .get(self.game.board.get(position).as_ref().unwrap())
-                    .unwrap();
-
-                graphics::draw(
-                    ctx,
-                    sprite,
-                    (ggez::mint::Point2 {
-                        x: ((position.file as i16 - 1) * GRID_CELL_SIZE.0) as f32, // Remove one as api i 1-8 instead of 0-7
-                        y: ((position.rank as i16 - 1) * GRID_CELL_SIZE.1) as f32,
-                    },),
-                )?;
+        // Fill each piece batch with one draw param per occupied square, then draw the batches.
+        let flipped = self.flipped();
+        for batch in self.sprite_batches.values_mut() {
+            batch.clear();
+        }
+        for (position, piece) in viewed_board.iter() {
+            if let Some(batch) = self.sprite_batches.get_mut(piece) {
+                let (col, row) = if flipped {
+                    (8 - position.file as i32, 8 - position.rank as i32)
+                } else {
+                    (position.file as i32 - 1, position.rank as i32 - 1)
+                };
+                batch.add(DrawParam::default().dest(ggez::mint::Point2 {
+                    x: (col * GRID_CELL_SIZE.0 as i32) as f32,
+                    y: (row * GRID_CELL_SIZE.1 as i32) as f32,
+                }));
             }
         }
+        for batch in self.sprite_batches.values() {
+            graphics::draw(ctx, batch, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+        }
 
         // Draw reset text
         let reset_text = graphics::Text::new(
-            graphics::TextFragment::from("Reset").scale(graphics::PxScale { x: 30.0, y: 30.0 }),
+            graphics::TextFragment::from(self.settings.language.reset())
+                .scale(graphics::PxScale { x: 30.0, y: 30.0 }),
         );
 
         graphics::draw(
@@ -213,7 +765,7 @@ impl event::EventHandler<ggez::GameError> for AppState {
 
         // Draw turn text
         let turn_text = graphics::Text::new(
-            graphics::TextFragment::from(format!("{:?}'s turn", self.game.active_color))
+            graphics::TextFragment::from(self.settings.language.turn(self.game.active_color))
                 .scale(graphics::PxScale { x: 30.0, y: 30.0 }),
         );
 
@@ -231,9 +783,32 @@ impl event::EventHandler<ggez::GameError> for AppState {
                 }),
         )?;
 
+        // Viewing-history banner shown while the cursor is on a past position
+        if !self.at_latest() {
+            let history_text = graphics::Text::new(
+                graphics::TextFragment::from(format!(
+                    "Viewing history {}/{}",
+                    self.cursor,
+                    self.history.len() - 1
+                ))
+                .scale(graphics::PxScale { x: 24.0, y: 24.0 }),
+            );
+
+            graphics::draw(
+                ctx,
+                &history_text,
+                DrawParam::default()
+                    .color([1.0, 0.0, 0.0, 1.0].into())
+                    .dest(ggez::mint::Point2 {
+                        x: (GRID_CELL_SIZE.0 * 8 + 10) as f32,
+                        y: (GRID_CELL_SIZE.1 * 5 + 10) as f32,
+                    }),
+            )?;
+        }
+
         // Promotion
         let promotion_text = graphics::Text::new(
-            graphics::TextFragment::from("Choose piece to promote to:")
+            graphics::TextFragment::from(self.settings.language.promote())
                 .scale(graphics::PxScale { x: 30.0, y: 30.0 }),
         );
 
@@ -253,7 +828,7 @@ impl event::EventHandler<ggez::GameError> for AppState {
         // Draw win text
         if self.game.get_game_state() == GameState::CheckMate {
             let win_text = graphics::Text::new(
-                graphics::TextFragment::from(format!("{:?} has won!", self.game.active_color))
+                graphics::TextFragment::from(self.settings.language.won(self.game.active_color))
                     .scale(graphics::PxScale { x: 60.0, y: 60.0 }),
             );
 
@@ -272,39 +847,61 @@ impl event::EventHandler<ggez::GameError> for AppState {
             )?;
         }
 
-        // Time text
-        let turn_text = graphics::Text::new(
-            graphics::TextFragment::from(format!(
-                "Time left: {}",
-                parse_time(match self.game.active_color {
-                    Colour::White => self.white_time,
-                    Colour::Black => self.black_time,
-                })
-            ))
-            .scale(graphics::PxScale { x: 30.0, y: 30.0 }),
+        // "Time left" label above the seven-segment digital clock.
+        let time_label = graphics::Text::new(
+            graphics::TextFragment::from(self.settings.language.time_left())
+                .scale(graphics::PxScale { x: 30.0, y: 30.0 }),
         );
 
+        let panel_x = (GRID_CELL_SIZE.0 * 8 + 10) as f32;
+        let panel_y = (GRID_CELL_SIZE.1 * 4 + 10) as f32;
         graphics::draw(
             ctx,
-            &turn_text,
+            &time_label,
             DrawParam::default()
                 .color(match self.game.active_color {
                     Colour::White => Color::WHITE,
                     Colour::Black => Color::BLACK,
                 })
                 .dest(ggez::mint::Point2 {
-                    x: (GRID_CELL_SIZE.0 * 8 + 10) as f32,
-                    y: (GRID_CELL_SIZE.1 * 4 + 10) as f32,
+                    x: panel_x,
+                    y: panel_y,
                 }),
         )?;
 
+        // Seven-segment clock for the active player, flashing the warning colour on low time.
+        let clock = self.clock(self.game.active_color);
+        let clock_colour = if clock.time < LOW_TIME_THRESHOLD {
+            Color::new(1.0, 0.0, 0.0, 1.0)
+        } else {
+            self.settings.palette().clock
+        };
+        draw_seven_segment_clock(ctx, clock.time, panel_x, panel_y + 36.0, clock_colour)?;
+
+        // Overtime counter (periods / stones) shown beside the clock when relevant.
+        if let Some(label) = clock.overtime_label(self.settings.time_control) {
+            let overtime_text = graphics::Text::new(
+                graphics::TextFragment::from(label)
+                    .scale(graphics::PxScale { x: 24.0, y: 24.0 }),
+            );
+            graphics::draw(
+                ctx,
+                &overtime_text,
+                DrawParam::default()
+                    .color(Color::WHITE)
+                    .dest(ggez::mint::Point2 {
+                        x: panel_x,
+                        y: panel_y + 100.0,
+                    }),
+            )?;
+        }
+
         // Draw time over text
-        if self.white_time == 0.0 || self.black_time == 0.0 {
+        if self.time_over() {
             let time_over_text = graphics::Text::new(
-                graphics::TextFragment::from(format!(
-                    "{:?} has won as the time ran out!",
-                    !self.game.active_color
-                ))
+                graphics::TextFragment::from(
+                    self.settings.language.timeout(!self.game.active_color),
+                )
                 .scale(graphics::PxScale { x: 30.0, y: 30.0 }),
             );
 
@@ -323,6 +920,80 @@ impl event::EventHandler<ggez::GameError> for AppState {
             )?;
         }
 
+        // Captured-piece tray: captured White pieces on one row, captured Black pieces on the next, scaled down.
+        let tray_y = (GRID_CELL_SIZE.1 * 6 + 30) as f32;
+        let tray_scale = 0.5;
+        let tray_step = GRID_CELL_SIZE.0 as f32 * tray_scale;
+        for (row, colour) in [Colour::White, Colour::Black].iter().enumerate() {
+            let mut slot = 0.0;
+            for piece in self.captured.iter() {
+                if get_colour_from_piece(*piece) != *colour {
+                    continue;
+                }
+                if let Some(sprite) = self.sprites.get(piece) {
+                    graphics::draw(
+                        ctx,
+                        sprite,
+                        DrawParam::default()
+                            .scale(ggez::mint::Vector2 {
+                                x: tray_scale,
+                                y: tray_scale,
+                            })
+                            .dest(ggez::mint::Point2 {
+                                x: (GRID_CELL_SIZE.0 * 8 + 10) as f32 + slot,
+                                y: tray_y + row as f32 * (tray_step + 4.0),
+                            }),
+                    )?;
+                    slot += tray_step;
+                }
+            }
+        }
+
+        // Settings overlay
+        if self.settings.open {
+            let overlay = graphics::Mesh::new_rectangle(
+                ctx,
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(0.0, 0.0, SCREEN_SIZE.0, SCREEN_SIZE.1),
+                Color::new(0.0, 0.0, 0.0, 0.7),
+            )?;
+            graphics::draw(ctx, &overlay, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+
+            let lines = [
+                "Settings (press M to close)".to_string(),
+                format!("[T] Theme: {}", self.settings.palette_name()),
+                format!(
+                    "[L] Language: {}",
+                    match self.settings.language {
+                        Language::English => "English",
+                        Language::Japanese => "日本語",
+                    }
+                ),
+                format!("[C] Time control: {}", self.settings.time_control_name()),
+                format!(
+                    "[A] Auto-flip board: {}",
+                    if self.settings.auto_flip { "on" } else { "off" }
+                ),
+                "(B flips the board, changing time control resets the clocks)".to_string(),
+            ];
+            for (row, line) in lines.iter().enumerate() {
+                let text = graphics::Text::new(
+                    graphics::TextFragment::from(line.to_owned())
+                        .scale(graphics::PxScale { x: 28.0, y: 28.0 }),
+                );
+                graphics::draw(
+                    ctx,
+                    &text,
+                    DrawParam::default()
+                        .color(Color::WHITE)
+                        .dest(ggez::mint::Point2 {
+                            x: 30.0,
+                            y: 30.0 + row as f32 * 40.0,
+                        }),
+                )?;
+            }
+        }
+
         // Render updated graphics
         graphics::present(ctx)?;
 
@@ -331,15 +1002,32 @@ impl event::EventHandler<ggez::GameError> for AppState {
 
     /// Update game on mouse click
     fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        // Ignore board interaction while the settings overlay is open.
+        if self.settings.open {
+            return;
+        }
+
         if button == MouseButton::Left {
             let x_tile = (x / GRID_CELL_SIZE.0 as f32) as i32;
             let y_tile = (y / GRID_CELL_SIZE.1 as f32) as i32;
 
             // Inside board
             if x_tile < 8 && y_tile < 8 {
-                let position = &Position {
-                    file: (x_tile + 1) as u8,
-                    rank: (y_tile + 1) as u8,
+                // Block moves while reviewing a past position; return to the latest first.
+                if !self.at_latest() {
+                    return;
+                }
+                // Convert the clicked cell to a board position, accounting for flipping.
+                let position = &if self.flipped() {
+                    Position {
+                        file: (8 - x_tile) as u8,
+                        rank: (8 - y_tile) as u8,
+                    }
+                } else {
+                    Position {
+                        file: (x_tile + 1) as u8,
+                        rank: (y_tile + 1) as u8,
+                    }
                 };
                 if self.game.board.contains_key(position) {
                     let piece = self.game.board.get(position).unwrap();
@@ -374,8 +1062,10 @@ impl event::EventHandler<ggez::GameError> for AppState {
                 self.game = Game::new();
                 self.selected_tile = None;
                 self.highlighted_tiles = Default::default();
-                self.white_time = 10.0 * 60.0;
-                self.black_time = 10.0 * 60.0;
+                self.reset_clocks();
+                self.move_log = Default::default();
+                self.captured = Default::default();
+                self.reset_history();
             } else if y_tile == 3 {
                 // Select promotion
                 let selected_piece = match x_tile {
@@ -399,26 +1089,95 @@ impl event::EventHandler<ggez::GameError> for AppState {
             self.highlighted_tiles = Default::default();
         }
     }
-}
 
-// Parses time from seconds to MM:SS:MSMS
-fn parse_time(time: f32) -> String {
-    let minutes = (time / 60.0).floor();
-    let seconds = (time - minutes * 60.0).floor();
-    let milliseconds = ((time - minutes * 60.0 - seconds) * 60.0).round();
+    /// Handle settings, navigation and import/export keyboard shortcuts.
+    fn key_down_event(
+        &mut self,
+        ctx: &mut Context,
+        keycode: KeyCode,
+        keymods: KeyMods,
+        repeat: bool,
+    ) {
+        if repeat {
+            return;
+        }
 
-    // Add 0 if less than 10
-    let mut second_string = seconds.to_string();
-    if second_string.len() == 1 {
-        second_string = format!("0{}", second_string);
-    }
+        // M toggles the settings overlay; while it is open the keys edit the settings.
+        if keycode == KeyCode::M {
+            self.settings.open = !self.settings.open;
+            return;
+        }
+        if self.settings.open {
+            match keycode {
+                KeyCode::T => {
+                    self.settings.cycle_palette();
+                    if let Ok(mesh) = AppState::build_board_mesh(ctx, self.settings.palette()) {
+                        self.board_mesh = mesh;
+                    }
+                }
+                KeyCode::L => self.settings.cycle_language(),
+                KeyCode::C => {
+                    // Re-init the clocks so period/stone counters match the new mode.
+                    self.settings.cycle_time_control();
+                    self.reset_clocks();
+                }
+                KeyCode::A => self.settings.auto_flip = !self.settings.auto_flip,
+                _ => {}
+            }
+            return;
+        }
+
+        // B flips the board perspective manually (ignored while auto-flip is on).
+        if keycode == KeyCode::B {
+            self.manual_flip = !self.manual_flip;
+            return;
+        }
+
+        // Left/Right step through the game history without any modifier.
+        match keycode {
+            KeyCode::Left => {
+                self.cursor = self.cursor.saturating_sub(1);
+                self.selected_tile = None;
+                self.highlighted_tiles = Default::default();
+                return;
+            }
+            KeyCode::Right => {
+                self.cursor = (self.cursor + 1).min(self.history.len() - 1);
+                self.selected_tile = None;
+                self.highlighted_tiles = Default::default();
+                return;
+            }
+            _ => {}
+        }
+
+        if !keymods.contains(KeyMods::CTRL) {
+            return;
+        }
 
-    let mut millisecond_string = milliseconds.to_string();
-    if millisecond_string.len() == 1 {
-        millisecond_string = format!("0{}", millisecond_string);
+        match keycode {
+            // Ctrl+S: save the current game to disk.
+            KeyCode::S => self.save_game(),
+            // Ctrl+O: reopen the last saved game and replay it.
+            KeyCode::O => self.open_game(),
+            // Ctrl+F: set up the board from a saved FEN position.
+            KeyCode::F => self.open_fen(),
+            _ => {}
+        }
     }
+}
 
-    return format!("{}:{}:{}", minutes, second_string, millisecond_string);
+// Base checkerboard colour for tile index `i` (0..64, row-major) under `palette`.
+fn checker_colour(i: i32, palette: Palette) -> Color {
+    match i % 2 {
+        0 => match i / 8 {
+            _row if _row % 2 == 0 => palette.light_tile,
+            _ => palette.dark_tile,
+        },
+        _ => match i / 8 {
+            _row if _row % 2 == 0 => palette.dark_tile,
+            _ => palette.light_tile,
+        },
+    }
 }
 
 fn draw_promotion_icons(state: &mut AppState, ctx: &mut Context) {
@@ -476,7 +1235,7 @@ fn draw_promotion_icon(
                 GRID_CELL_SIZE.0 as i32,
                 GRID_CELL_SIZE.1 as i32,
             ),
-            SELECTED_TILE,
+            state.settings.palette().selected,
         )
         .unwrap();
         graphics::draw(ctx, &rectangle, (ggez::mint::Point2 { x: 0.0, y: 0.0 },)).unwrap();
@@ -494,20 +1253,110 @@ fn draw_promotion_icon(
     .unwrap();
 }
 
+/// Draw an `MM:SS` seven-segment clock with its top-left corner at `(x, y)`.
+///
+/// The display flashes (blanks every other half-second) while `seconds` is below
+/// [`LOW_TIME_THRESHOLD`]; `colour` is used for every lit segment and the colon.
+fn draw_seven_segment_clock(
+    ctx: &mut Context,
+    seconds: f32,
+    x: f32,
+    y: f32,
+    colour: Color,
+) -> GameResult {
+    // Never render a negative readout.
+    let seconds = seconds.max(0.0);
+
+    // Blink off during the second half of each second when low on time.
+    if seconds < LOW_TIME_THRESHOLD && seconds - seconds.floor() >= 0.5 {
+        return Ok(());
+    }
+
+    let minutes = (seconds / 60.0).floor() as i32;
+    let secs = (seconds - minutes as f32 * 60.0).floor() as i32;
+    let digits = [minutes / 10, minutes % 10, secs / 10, secs % 10];
+
+    let digit_width = 28.0;
+    let spacing = 8.0;
+    let colon_width = 14.0;
+    let mut cursor = x;
+    for (i, digit) in digits.iter().enumerate() {
+        draw_digit(ctx, *digit as usize, cursor, y, colour)?;
+        cursor += digit_width + spacing;
+        // Colon separator between MM and SS.
+        if i == 1 {
+            draw_colon(ctx, cursor, y, colour)?;
+            cursor += colon_width + spacing;
+        }
+    }
+    Ok(())
+}
+
+/// Draw a single seven-segment `digit` (0-9) at `(x, y)`.
+fn draw_digit(ctx: &mut Context, digit: usize, x: f32, y: f32, colour: Color) -> GameResult {
+    let w = 28.0;
+    let h = 56.0;
+    let t = 6.0;
+    let half = (h - 3.0 * t) / 2.0;
+
+    // Segment rectangles in order a, b, c, d, e, f, g.
+    let segments = [
+        graphics::Rect::new(x + t, y, w - 2.0 * t, t),                    // a (top)
+        graphics::Rect::new(x + w - t, y + t, t, half),                  // b (top-right)
+        graphics::Rect::new(x + w - t, y + 2.0 * t + half, t, half),     // c (bottom-right)
+        graphics::Rect::new(x + t, y + 2.0 * t + 2.0 * half, w - 2.0 * t, t), // d (bottom)
+        graphics::Rect::new(x, y + 2.0 * t + half, t, half),             // e (bottom-left)
+        graphics::Rect::new(x, y + t, t, half),                          // f (top-left)
+        graphics::Rect::new(x + t, y + t + half, w - 2.0 * t, t),        // g (middle)
+    ];
+
+    let lit = DIGIT_SEGMENTS[digit];
+    for (segment, on) in segments.iter().zip(lit.iter()) {
+        if *on {
+            let mesh =
+                graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), *segment, colour)?;
+            graphics::draw(ctx, &mesh, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+        }
+    }
+    Ok(())
+}
+
+/// Draw the two dots of the `MM:SS` colon separator at `(x, y)`.
+fn draw_colon(ctx: &mut Context, x: f32, y: f32, colour: Color) -> GameResult {
+    let t = 6.0;
+    for offset in [20.0, 40.0] {
+        let dot = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(x, y + offset, t, t),
+            colour,
+        )?;
+        graphics::draw(ctx, &dot, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+    }
+    Ok(())
+}
+
 fn move_to_tile(state: &mut AppState, position: &Position) {
     // Prevent moving when time is over
-    if state.white_time == 0.0 || state.black_time == 0.0 {
+    if state.time_over() {
         return;
     }
 
     if state.highlighted_tiles.contains(position) {
-        state
-            .game
-            .make_move(
-                state.selected_tile.unwrap().to_string(),
-                position.to_string(),
-            )
-            .unwrap();
+        let from = state.selected_tile.unwrap().to_string();
+        let to = position.to_string();
+        // The player completing the move gets the time control's reward applied.
+        let mover = state.game.active_color;
+        let before = state.game.board.clone();
+        state.game.make_move(from.clone(), to.clone()).unwrap();
+        record_captures(state, &before);
+        let control = state.settings.time_control;
+        match mover {
+            Colour::White => state.white_clock.on_move(control),
+            Colour::Black => state.black_clock.on_move(control),
+        }
+        state.move_log.push((from, to));
+        state.record_snapshot();
 
         // Deselect tile
         state.selected_tile = None;
@@ -515,6 +1364,106 @@ fn move_to_tile(state: &mut AppState, position: &Position) {
     }
 }
 
+// Append any pieces that vanished from the board to the captured tray by diffing
+// the piece multiset before and after the move. (A promotion is reported as a
+// captured pawn, which is a fair approximation for the material tray.)
+fn record_captures(state: &mut AppState, before: &HashMap<Position, PieceType>) {
+    let mut counts: HashMap<PieceType, i32> = HashMap::new();
+    for piece in before.values() {
+        *counts.entry(*piece).or_insert(0) += 1;
+    }
+    for piece in state.game.board.values() {
+        *counts.entry(*piece).or_insert(0) -= 1;
+    }
+    for (piece, removed) in counts {
+        for _ in 0..removed.max(0) {
+            state.captured.push(piece);
+        }
+    }
+}
+
+// Serialises a board into the piece-placement field of a FEN string (rank 8 first).
+fn placement_from_board(board: &HashMap<Position, PieceType>) -> String {
+    let mut placement = String::new();
+    for rank in (1..=8).rev() {
+        let mut empty = 0;
+        for file in 1..=8 {
+            let position = Position { file, rank };
+            if let Some(piece) = board.get(&position) {
+                if empty > 0 {
+                    placement.push_str(&empty.to_string());
+                    empty = 0;
+                }
+                placement.push(piece_to_fen_char(piece.to_owned()));
+            } else {
+                empty += 1;
+            }
+        }
+        if empty > 0 {
+            placement.push_str(&empty.to_string());
+        }
+        if rank > 1 {
+            placement.push('/');
+        }
+    }
+    placement
+}
+
+// Parses the piece-placement field of a FEN string back into a board.
+fn board_from_placement(placement: &str) -> HashMap<Position, PieceType> {
+    let mut board: HashMap<Position, PieceType> = Default::default();
+    for (row, line) in placement.split('/').enumerate() {
+        let rank = (8 - row) as u8;
+        let mut file: u8 = 1;
+        for symbol in line.chars() {
+            if let Some(skip) = symbol.to_digit(10) {
+                file += skip as u8;
+            } else if let Some(piece) = fen_char_to_piece(symbol) {
+                board.insert(Position { file, rank }, piece);
+                file += 1;
+            }
+        }
+    }
+    board
+}
+
+// Maps a chess piece to its FEN letter (upper case for White, lower for Black).
+fn piece_to_fen_char(piece: PieceType) -> char {
+    use PieceType::*;
+    let symbol = match piece {
+        King(_) => 'k',
+        Queen(_) => 'q',
+        Rook(_) => 'r',
+        Bishop(_) => 'b',
+        Knight(_) => 'n',
+        Pawn(_) => 'p',
+    };
+    if get_colour_from_piece(piece) == Colour::White {
+        symbol.to_ascii_uppercase()
+    } else {
+        symbol
+    }
+}
+
+// Maps a FEN letter back to a chess piece, or `None` for an unknown symbol.
+fn fen_char_to_piece(symbol: char) -> Option<PieceType> {
+    use PieceType::*;
+    let colour = if symbol.is_ascii_uppercase() {
+        Colour::White
+    } else {
+        Colour::Black
+    };
+    Some(match symbol.to_ascii_lowercase() {
+        'k' => King(colour),
+        'q' => Queen(colour),
+        'r' => Rook(colour),
+        'b' => Bishop(colour),
+        'n' => Knight(colour),
+        'p' => Pawn(colour),
+        _ => return None,
+    })
+}
+
 // Elias why didn't you make the Piece::color method public!?
 fn get_colour_from_piece(piece: PieceType) -> Colour {
     use Colour::*;
@@ -547,3 +1496,107 @@ pub fn main() -> GameResult {
     let state = AppState::new(&mut context)?;
     event::run(context, event_loop, state) // Run window event loop
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placement_round_trips_through_the_board() {
+        // Standard starting position, piece-placement field only.
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR";
+        let board = board_from_placement(start);
+        assert_eq!(board.len(), 32);
+        assert_eq!(placement_from_board(&board), start);
+    }
+
+    #[test]
+    fn fen_char_maps_both_ways() {
+        for symbol in ['K', 'Q', 'R', 'B', 'N', 'P', 'k', 'q', 'r', 'b', 'n', 'p'] {
+            let piece = fen_char_to_piece(symbol).unwrap();
+            assert_eq!(piece_to_fen_char(piece), symbol);
+        }
+        assert!(fen_char_to_piece('x').is_none());
+    }
+
+    #[test]
+    fn fischer_increment_is_added_on_move() {
+        let control = TimeControl::Fischer { increment: 5.0 };
+        let mut clock = PlayerClock::new(control);
+        clock.time = 100.0;
+        clock.on_move(control);
+        assert_eq!(clock.time, 105.0);
+    }
+
+    #[test]
+    fn byoyomi_enters_overtime_then_counts_down_periods() {
+        let control = TimeControl::ByoYomi { period: 30.0 };
+        let mut clock = PlayerClock::new(control);
+        assert_eq!(clock.periods, BYOYOMI_PERIODS);
+
+        // Run out the (tiny) main time and drop into the first period.
+        clock.time = 1.0;
+        clock.tick(2.0, control);
+        assert!(clock.in_overtime);
+        assert_eq!(clock.periods, BYOYOMI_PERIODS);
+        assert_eq!(clock.time, 30.0);
+
+        // Completing a move resets the current period.
+        clock.time = 4.0;
+        clock.on_move(control);
+        assert_eq!(clock.time, 30.0);
+
+        // Letting periods expire consumes them; the last expiry flags the player.
+        clock.time = 1.0;
+        clock.tick(2.0, control);
+        assert_eq!(clock.periods, BYOYOMI_PERIODS - 1);
+        assert_eq!(clock.time, 30.0);
+        clock.periods = 1;
+        clock.time = 1.0;
+        clock.tick(2.0, control);
+        assert!(clock.flagged);
+        assert_eq!(clock.time, 0.0);
+    }
+
+    #[test]
+    fn canadian_resets_block_after_the_required_moves() {
+        let control = TimeControl::Canadian {
+            block: 60.0,
+            moves: 2,
+        };
+        let mut clock = PlayerClock::new(control);
+
+        // Main time expiry opens the first block.
+        clock.time = 1.0;
+        clock.tick(2.0, control);
+        assert!(clock.in_overtime);
+        assert_eq!(clock.stones, 2);
+        assert_eq!(clock.time, 60.0);
+
+        // First move just decrements the stone count.
+        clock.time = 10.0;
+        clock.on_move(control);
+        assert_eq!(clock.stones, 1);
+        assert_eq!(clock.time, 10.0);
+
+        // Completing the block's last move refills stones and time.
+        clock.on_move(control);
+        assert_eq!(clock.stones, 2);
+        assert_eq!(clock.time, 60.0);
+    }
+
+    #[test]
+    fn canadian_block_expiry_flags_and_clamps() {
+        let control = TimeControl::Canadian {
+            block: 60.0,
+            moves: 2,
+        };
+        let mut clock = PlayerClock::new(control);
+        clock.in_overtime = true;
+        clock.stones = 2;
+        clock.time = 1.0;
+        clock.tick(2.0, control);
+        assert!(clock.flagged);
+        assert_eq!(clock.time, 0.0);
+    }
+}